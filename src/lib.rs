@@ -5,10 +5,19 @@
 
 #![warn(missing_docs)]
 
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
 use std::process::Command;
-use std::io::Write;
+use std::time::Duration;
+use rand::Rng;
 use tempfile::NamedTempFile;
 
+mod manifest;
+pub use manifest::{blake3_hex, ChunkStatus, Manifest, ManifestHeader};
+
+mod verify;
+pub use verify::{verify_chunk, UploadReceipt};
 
 /// The maximum size of the HTTP payload for canister updates, set to 2 MiB.
 pub const MAX_CANISTER_HTTP_PAYLOAD_SIZE: usize = 2 * 1000 * 1000; // 2 MiB
@@ -34,6 +43,39 @@ pub fn split_into_chunks(data: Vec<u8>, chunk_size: usize, start_ind: usize) ->
         .collect()
 }
 
+/// Reads the file at `path` one `chunk_size` buffer at a time, starting
+/// at byte `start_ind`, instead of materializing the whole file in memory.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to read chunks from.
+/// * `chunk_size` - The size of each chunk.
+/// * `start_ind` - The starting byte offset for chunking.
+///
+/// # Returns
+///
+/// An iterator yielding one chunk at a time, only reading the next chunk
+/// from disk once the caller asks for it, so at most one chunk per
+/// in-flight upload is resident in memory.
+pub fn chunk_reader(
+    path: &Path,
+    chunk_size: usize,
+    start_ind: usize,
+) -> io::Result<impl Iterator<Item = io::Result<Vec<u8>>>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start_ind as u64))?;
+    let mut reader = BufReader::new(file);
+
+    Ok(std::iter::from_fn(move || {
+        let mut buf = Vec::with_capacity(chunk_size);
+        match reader.by_ref().take(chunk_size as u64).read_to_end(&mut buf) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(buf)),
+            Err(e) => Some(Err(e)),
+        }
+    }))
+}
+
 /// Converts a vector of bytes to a blob string.
 ///
 /// # Arguments
@@ -48,7 +90,8 @@ pub fn vec_u8_to_blob_string(data: &[u8]) -> String {
     format!("(blob \"{}\")", blob_content)
 }
 
-/// Uploads a chunk of data to the specified canister method.
+/// Uploads a chunk of data to the specified canister method, retrying
+/// transient `dfx` failures with exponential backoff.
 ///
 /// # Arguments
 ///
@@ -59,10 +102,14 @@ pub fn vec_u8_to_blob_string(data: &[u8]) -> String {
 /// * `chunk_number` - The number of the current chunk.
 /// * `chunk_total` - The total number of chunks.
 /// * `network` - An optional network type.
+/// * `max_retries` - How many additional attempts to make after the first failure.
+/// * `retry_base_delay_ms` - The base delay used to compute `base * 2^attempt`, plus jitter.
 ///
 /// # Returns
 ///
-/// A `Result` indicating success (`Ok(())`) or an error message (`Err(String)`).
+/// A `Result` indicating success (`Ok(())`) or an error message (`Err(String)`)
+/// once every attempt, including retries, has failed.
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_chunk(
     canister_name: &str,
     bytecode_chunk: Vec<u8>,
@@ -71,6 +118,8 @@ pub async fn upload_chunk(
     chunk_total: usize,
     network: Option<&str>,
     concurrent: bool,
+    max_retries: usize,
+    retry_base_delay_ms: u64,
 ) -> Result<(), String> {
     // Convert to blob string
     let blob_string = vec_u8_to_blob_string(&bytecode_chunk);
@@ -100,8 +149,37 @@ pub async fn upload_chunk(
         ]
     };
 
-    // Execute dfx command
-    let output = dfx("canister", "call", &args, network)?;
+    let mut attempt = 0;
+    loop {
+        match try_upload_chunk(&args, network, index, chunk_total) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < max_retries => {
+                let delay_ms = retry_base_delay_ms.saturating_mul(1u64 << attempt.min(63))
+                    + rand::thread_rng().gen_range(0..=retry_base_delay_ms.max(1));
+                eprintln!(
+                    "Retrying chunk {} (attempt {}/{}) in {}ms after error: {}",
+                    index + 1,
+                    attempt + 1,
+                    max_retries,
+                    delay_ms,
+                    e
+                );
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Makes a single attempt to upload a chunk via `dfx`, with no retry logic.
+fn try_upload_chunk(
+    args: &Vec<&str>,
+    network: Option<&str>,
+    index: usize,
+    chunk_total: usize,
+) -> Result<(), String> {
+    let output = dfx("canister", "call", args, network)?;
 
     if output.status.success() {
         println!("Uploaded chunk {}/{}", index + 1, chunk_total);
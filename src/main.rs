@@ -7,10 +7,15 @@
 //! the canister name, method name, file path, and network type.
 
 use std::fs;
+use std::sync::Arc;
 use clap::Parser;
 use std::path::Path;
-use ic_file_uploader::{split_into_chunks, upload_chunk, MAX_CANISTER_HTTP_PAYLOAD_SIZE};
+use ic_file_uploader::{
+    blake3_hex, chunk_reader, upload_chunk, verify_chunk, Manifest, ManifestHeader,
+    UploadReceipt, MAX_CANISTER_HTTP_PAYLOAD_SIZE,
+};
 use futures::{stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
 
 /// Command line arguments for the ic-file-uploader
 #[derive(Parser, Debug)]
@@ -36,9 +41,18 @@ struct Args {
     #[arg(short, long)]
     network: Option<String>,
 
-    /// Enable autoresume (optional, not yet implemented)
-    #[arg(short, long, hide = true)]
-    _autoresume: bool,
+    /// Resume a previously interrupted upload using the `<file_path>.icupload` manifest,
+    /// skipping any chunk already recorded as uploaded with a matching blake3 digest
+    #[arg(short, long)]
+    autoresume: bool,
+
+    /// Only re-upload chunks whose blake3 digest differs from the `<file_path>.icupload`
+    /// manifest of a previous run, skipping any chunk that's unchanged at the same index.
+    /// Note: the manifest also records the file's total length, so if the new file isn't
+    /// exactly the same length as the one the manifest was written for, it's treated as
+    /// stale and every chunk is re-uploaded instead of diffed
+    #[arg(short, long)]
+    incremental: bool,
 
     /// Enable concurrent uploads
     #[arg(long, default_value_t = false)]
@@ -47,6 +61,22 @@ struct Args {
     /// Number of concurrent uploads (default: 5)
     #[arg(long, default_value = "5")]
     concurrent_uploads: usize,
+
+    /// Maximum number of retries for a chunk after a failed `dfx` call
+    #[arg(long, default_value = "3")]
+    max_retries: usize,
+
+    /// Base delay, in milliseconds, for exponential backoff between retries
+    #[arg(long, default_value = "500")]
+    retry_base_delay_ms: u64,
+
+    /// Run a post-upload verification pass and write a whole-file checksum receipt
+    #[arg(long, default_value_t = false)]
+    verify: bool,
+
+    /// Canister query method used to verify each chunk (required with --verify)
+    #[arg(long)]
+    verify_method: Option<String>,
 }
 
 
@@ -61,32 +91,46 @@ async fn main() -> Result<(), String> {
     let bytes_path = Path::new(&args.file_path);
     println!("Uploading {}", args.file_path);
 
-    let model_data = fs::read(&bytes_path).map_err(|e| e.to_string())?;
-    let model_chunks = split_into_chunks(model_data, MAX_CANISTER_HTTP_PAYLOAD_SIZE, args.offset);
+    let total_len = fs::metadata(&bytes_path).map_err(|e| e.to_string())?.len();
+    let model_chunks_len = chunk_count(total_len, args.offset, MAX_CANISTER_HTTP_PAYLOAD_SIZE);
 
-    // TODO: Implement autoresume functionality using the args.autoresume flag
-    let model_chunks_len = model_chunks.len();
-    if args.concurrent {
-        /*
-        let upload_futures = model_chunks.clone().into_iter().enumerate().map(|(index, chunk)| {
-            upload_chunk(
-                &args.canister_name,
-                chunk,
-                &args.canister_method,
-                index,
-                model_chunks_len,
-                args.network.as_deref(),
-                true,
-            )
-        });
-        */
-        let upload_futures = model_chunks.into_iter().enumerate().map(|(index, chunk)| {
+    let manifest = if args.autoresume || args.incremental {
+        let header = ManifestHeader {
+            canister_name: args.canister_name.clone(),
+            canister_method: args.canister_method.clone(),
+            chunk_size: MAX_CANISTER_HTTP_PAYLOAD_SIZE,
+            total_len,
+        };
+        Some(Arc::new(Manifest::open(bytes_path, header)?))
+    } else {
+        None
+    };
+
+    let pb = ProgressBar::new(total_len.saturating_sub(args.offset as u64));
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{bar:40.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta})",
+        )
+        .map_err(|e| e.to_string())?
+        .progress_chars("=>-"),
+    );
+
+    let outcomes = if args.concurrent {
+        let chunks = chunk_reader(bytes_path, MAX_CANISTER_HTTP_PAYLOAD_SIZE, args.offset)
+            .map_err(|e| e.to_string())?;
+        let upload_futures = chunks.enumerate().map(|(index, chunk)| {
             let canister_name = args.canister_name.clone();
             let canister_method = args.canister_method.clone();
             let network = args.network.clone();
+            let manifest = manifest.clone();
+            let max_retries = args.max_retries;
+            let retry_base_delay_ms = args.retry_base_delay_ms;
+            let pb = pb.clone();
 
             async move {
-                upload_chunk(
+                let chunk = chunk.map_err(|e| e.to_string())?;
+                let chunk_len = chunk.len() as u64;
+                let result = upload_chunk_resumable(
                     &canister_name,
                     chunk,
                     &canister_method,
@@ -94,7 +138,14 @@ async fn main() -> Result<(), String> {
                     model_chunks_len,
                     network.as_deref(),
                     true,
-                ).await
+                    manifest.as_deref(),
+                    max_retries,
+                    retry_base_delay_ms,
+                ).await;
+                if result.is_ok() {
+                    pb.inc(chunk_len);
+                }
+                result
             }
         });
 
@@ -103,16 +154,26 @@ async fn main() -> Result<(), String> {
             .collect::<Vec<_>>()
             .await;
 
+        let mut outcomes = Vec::with_capacity(results.len());
         for (index, result) in results.into_iter().enumerate() {
-            if let Err(e) = result {
-                eprintln!("Error uploading chunk {}: {}", index, e);
-                return Err(format!("Upload interrupted at chunk {}: {}", index, e));
+            match result {
+                Ok(skipped) => outcomes.push(skipped),
+                Err(e) => {
+                    eprintln!("Error uploading chunk {}: {}", index, e);
+                    return Err(format!("Upload interrupted at chunk {}: {}", index, e));
+                }
             }
         }
+        outcomes
 
     } else {
-       for (index, chunk) in model_chunks.into_iter().enumerate() {
-            if let Err(e) = upload_chunk(
+        let chunks = chunk_reader(bytes_path, MAX_CANISTER_HTTP_PAYLOAD_SIZE, args.offset)
+            .map_err(|e| e.to_string())?;
+        let mut outcomes = Vec::with_capacity(model_chunks_len);
+        for (index, chunk) in chunks.enumerate() {
+            let chunk = chunk.map_err(|e| e.to_string())?;
+            let chunk_len = chunk.len() as u64;
+            match upload_chunk_resumable(
                 &args.canister_name,
                 chunk,
                 &args.canister_method,
@@ -120,14 +181,125 @@ async fn main() -> Result<(), String> {
                 model_chunks_len,
                 args.network.as_deref(),
                 false,
+                manifest.as_deref(),
+                args.max_retries,
+                args.retry_base_delay_ms,
             ).await {
-                eprintln!("Error uploading chunk {}: {}", index, e);
-                return Err(format!("Upload interrupted at chunk {}: {}", index, e));
+                Ok(skipped) => {
+                    pb.inc(chunk_len);
+                    outcomes.push(skipped);
+                }
+                Err(e) => {
+                    eprintln!("Error uploading chunk {}: {}", index, e);
+                    return Err(format!("Upload interrupted at chunk {}: {}", index, e));
+                }
+            }
+        }
+        outcomes
+    };
+
+    pb.finish_with_message("Upload complete");
+
+    if args.incremental {
+        let unchanged = outcomes.iter().filter(|&&skipped| skipped).count();
+        let reuploaded = outcomes.len() - unchanged;
+        println!(
+            "re-uploaded {}/{} chunks, {} unchanged",
+            reuploaded,
+            outcomes.len(),
+            unchanged
+        );
+    }
+
+    if args.verify {
+        let verify_method = args
+            .verify_method
+            .as_deref()
+            .ok_or_else(|| "--verify requires --verify-method".to_string())?;
+
+        let chunks = chunk_reader(bytes_path, MAX_CANISTER_HTTP_PAYLOAD_SIZE, args.offset)
+            .map_err(|e| e.to_string())?;
+        let mut mismatches = 0;
+        for (index, chunk) in chunks.enumerate() {
+            let chunk = chunk.map_err(|e| e.to_string())?;
+            let digest = blake3_hex(&chunk);
+            if let Err(e) = verify_chunk(
+                &args.canister_name,
+                verify_method,
+                index,
+                chunk.len() as u64,
+                &digest,
+                args.network.as_deref(),
+            ) {
+                eprintln!("{e}");
+                mismatches += 1;
             }
         }
+        println!("Verified {} chunks, {} mismatches", model_chunks_len, mismatches);
+
+        let receipt = UploadReceipt::for_file(bytes_path).map_err(|e| e.to_string())?;
+        println!("{}", receipt.as_line(&args.file_path));
+        receipt.write(bytes_path).map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
+/// Returns how many `chunk_size` chunks cover `[offset, total_len)`.
+fn chunk_count(total_len: u64, offset: usize, chunk_size: usize) -> usize {
+    let remaining = total_len.saturating_sub(offset as u64);
+    if remaining == 0 {
+        0
+    } else {
+        ((remaining - 1) / chunk_size as u64 + 1) as usize
+    }
+}
+
+/// Uploads a single chunk, honoring an optional resume/incremental `manifest`.
+///
+/// If the chunk at `index` was already uploaded with a matching blake3
+/// digest, the `dfx` call is skipped entirely and `Ok(true)` is returned.
+/// Otherwise the chunk is uploaded as usual, recorded in the manifest on
+/// success, and `Ok(false)` is returned.
+#[allow(clippy::too_many_arguments)]
+async fn upload_chunk_resumable(
+    canister_name: &str,
+    bytecode_chunk: Vec<u8>,
+    canister_method_name: &str,
+    index: usize,
+    chunk_total: usize,
+    network: Option<&str>,
+    concurrent: bool,
+    manifest: Option<&Manifest>,
+    max_retries: usize,
+    retry_base_delay_ms: u64,
+) -> Result<bool, String> {
+    let digest = manifest.map(|_| blake3_hex(&bytecode_chunk));
+
+    if let (Some(manifest), Some(digest)) = (manifest, digest.as_deref()) {
+        if manifest.is_uploaded(index, digest) {
+            println!("Skipping chunk {}/{} (already uploaded)", index + 1, chunk_total);
+            return Ok(true);
+        }
+    }
+
+    upload_chunk(
+        canister_name,
+        bytecode_chunk,
+        canister_method_name,
+        index,
+        chunk_total,
+        network,
+        concurrent,
+        max_retries,
+        retry_base_delay_ms,
+    ).await?;
+
+    if let (Some(manifest), Some(digest)) = (manifest, digest.as_deref()) {
+        manifest.record_uploaded(index, digest)?;
+    }
+
+    Ok(false)
+}
+
 
@@ -0,0 +1,179 @@
+//! A persistent, human-readable sidecar manifest that records which chunks
+//! of a file have already been uploaded, so an interrupted upload can
+//! resume instead of restarting from chunk 0.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::create_error_string;
+
+/// The status recorded for a single chunk line in the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkStatus {
+    /// The chunk was uploaded and `dfx` reported success.
+    Uploaded,
+}
+
+impl ChunkStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ChunkStatus::Uploaded => "uploaded",
+        }
+    }
+}
+
+/// The header metadata stored at the top of a manifest file.
+///
+/// A manifest is only resumed from if every field here matches the
+/// current run; otherwise it's treated as stale and ignored, since
+/// resuming into the wrong canister or file would silently corrupt data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestHeader {
+    /// The canister the chunks were (or will be) uploaded to.
+    pub canister_name: String,
+    /// The canister method the chunks were (or will be) uploaded with.
+    pub canister_method: String,
+    /// The chunk size used to split the file.
+    pub chunk_size: usize,
+    /// The total length of the file being uploaded, in bytes.
+    pub total_len: u64,
+}
+
+impl ManifestHeader {
+    fn to_lines(&self) -> String {
+        format!(
+            "# canister_name={}\n# canister_method={}\n# chunk_size={}\n# total_len={}\n",
+            self.canister_name, self.canister_method, self.chunk_size, self.total_len
+        )
+    }
+}
+
+/// A persistent record of which chunks of a file have already been
+/// uploaded, keyed by chunk index and blake3 digest.
+///
+/// The manifest lives next to the uploaded file as `<file_path>.icupload`,
+/// one line per chunk in the form `index\tblake3hex\tstatus`, and is
+/// flushed after every successful chunk upload.
+pub struct Manifest {
+    file: Mutex<File>,
+    uploaded: HashMap<usize, String>,
+}
+
+impl Manifest {
+    /// Returns the manifest path that sits alongside `file_path`.
+    pub fn path_for(file_path: &Path) -> PathBuf {
+        let mut path = file_path.as_os_str().to_owned();
+        path.push(".icupload");
+        PathBuf::from(path)
+    }
+
+    /// Opens the manifest for `file_path`, resuming from it if its header
+    /// matches `header`, or starting a fresh one otherwise.
+    pub fn open(file_path: &Path, header: ManifestHeader) -> Result<Self, String> {
+        let manifest_path = Self::path_for(file_path);
+        let uploaded = fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|contents| parse_manifest(&contents, &header));
+
+        let is_fresh = uploaded.is_none();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(&manifest_path)
+            .map_err(|e| create_error_string(&format!("Failed to open manifest: {e}")))?;
+
+        if is_fresh {
+            file.set_len(0)
+                .map_err(|e| create_error_string(&format!("Failed to truncate manifest: {e}")))?;
+            file.write_all(header.to_lines().as_bytes())
+                .map_err(|e| create_error_string(&format!("Failed to write manifest header: {e}")))?;
+            file.flush()
+                .map_err(|e| create_error_string(&format!("Failed to flush manifest header: {e}")))?;
+        }
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&manifest_path)
+            .map_err(|e| create_error_string(&format!("Failed to reopen manifest: {e}")))?;
+
+        Ok(Manifest {
+            file: Mutex::new(file),
+            uploaded: uploaded.unwrap_or_default(),
+        })
+    }
+
+    /// Returns `true` if `index` was already uploaded with exactly this
+    /// `digest`, meaning the chunk can be skipped this run.
+    pub fn is_uploaded(&self, index: usize, digest: &str) -> bool {
+        self.uploaded.get(&index).map(String::as_str) == Some(digest)
+    }
+
+    /// Returns the previously recorded digest for `index`, if any.
+    pub fn digest_at(&self, index: usize) -> Option<&str> {
+        self.uploaded.get(&index).map(String::as_str)
+    }
+
+    /// Appends and durably flushes a line recording `index` as uploaded
+    /// with the given `digest`. Safe to call from concurrent tasks: writes
+    /// are serialized behind an internal mutex so interleaved completions
+    /// can't corrupt a line.
+    pub fn record_uploaded(&self, index: usize, digest: &str) -> Result<(), String> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| create_error_string("Manifest lock was poisoned"))?;
+        writeln!(file, "{}\t{}\t{}", index, digest, ChunkStatus::Uploaded.as_str())
+            .map_err(|e| create_error_string(&format!("Failed to write manifest line: {e}")))?;
+        file.flush()
+            .map_err(|e| create_error_string(&format!("Failed to flush manifest: {e}")))?;
+        file.sync_data()
+            .map_err(|e| create_error_string(&format!("Failed to sync manifest: {e}")))?;
+        Ok(())
+    }
+}
+
+/// Parses a manifest's contents, returning the uploaded chunk digests if
+/// the header matches `expected`, or `None` if it's missing or stale.
+fn parse_manifest(contents: &str, expected: &ManifestHeader) -> Option<HashMap<usize, String>> {
+    let mut found = ManifestHeader {
+        canister_name: String::new(),
+        canister_method: String::new(),
+        chunk_size: 0,
+        total_len: 0,
+    };
+    let mut uploaded = HashMap::new();
+
+    for line in contents.lines() {
+        if let Some(value) = line.strip_prefix("# canister_name=") {
+            found.canister_name = value.to_string();
+        } else if let Some(value) = line.strip_prefix("# canister_method=") {
+            found.canister_method = value.to_string();
+        } else if let Some(value) = line.strip_prefix("# chunk_size=") {
+            found.chunk_size = value.parse().ok()?;
+        } else if let Some(value) = line.strip_prefix("# total_len=") {
+            found.total_len = value.parse().ok()?;
+        } else if !line.is_empty() {
+            let mut fields = line.splitn(3, '\t');
+            let index: usize = fields.next()?.parse().ok()?;
+            let digest = fields.next()?.to_string();
+            let status = fields.next()?;
+            if status == ChunkStatus::Uploaded.as_str() {
+                uploaded.insert(index, digest);
+            }
+        }
+    }
+
+    if &found == expected {
+        Some(uploaded)
+    } else {
+        None
+    }
+}
+
+/// Returns the blake3 digest of `data` as a lowercase hex string.
+pub fn blake3_hex(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
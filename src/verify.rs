@@ -0,0 +1,131 @@
+//! Post-upload verification: querying the canister for what it actually
+//! stored, and a whole-file checksum receipt the user can keep as proof.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{create_error_string, dfx};
+
+/// Queries `verify_method_name` for chunk `index` and checks that the
+/// canister's reported stored length and blake3 digest match what was
+/// uploaded locally.
+pub fn verify_chunk(
+    canister_name: &str,
+    verify_method_name: &str,
+    index: usize,
+    expected_len: u64,
+    expected_digest: &str,
+    network: Option<&str>,
+) -> Result<(), String> {
+    let index_arg = format!("({})", index);
+    let args = vec![canister_name, verify_method_name, "--query", "--argument", &index_arg];
+    let output = dfx("canister", "call", &args, network)?;
+
+    if !output.status.success() {
+        return Err(create_error_string(&format!(
+            "Verify call for chunk {} failed: {}",
+            index + 1,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let reply = String::from_utf8_lossy(&output.stdout);
+    let (stored_len, stored_digest) = parse_verify_reply(&reply)?;
+
+    if stored_len != expected_len || stored_digest != expected_digest {
+        return Err(create_error_string(&format!(
+            "Chunk {} mismatch: canister reports len={} digest={}, expected len={} digest={}",
+            index + 1,
+            stored_len,
+            stored_digest,
+            expected_len,
+            expected_digest
+        )));
+    }
+
+    Ok(())
+}
+
+/// Pulls the stored length and digest out of a `dfx canister call --query`
+/// text reply, e.g. `(record { len = 2_000_000 : nat64; digest = "ab12..." })`.
+fn parse_verify_reply(reply: &str) -> Result<(u64, String), String> {
+    let after_digest_field = reply
+        .split_once("digest = \"")
+        .ok_or_else(|| create_error_string("Verify reply did not contain a digest field"))?
+        .1;
+    let digest = after_digest_field
+        .split('"')
+        .next()
+        .ok_or_else(|| create_error_string("Verify reply did not contain a digest string"))?
+        .to_string();
+
+    let after_len_field = reply
+        .split_once("len = ")
+        .ok_or_else(|| create_error_string("Verify reply did not contain a len field"))?
+        .1;
+    let digits: String = after_len_field
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '_')
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    let stored_len: u64 = digits
+        .parse()
+        .map_err(|_| create_error_string("Verify reply did not contain a stored length"))?;
+
+    Ok((stored_len, digest))
+}
+
+/// A whole-file receipt recording the total bytes uploaded and a blake3
+/// checksum over the entire file, so a user can later confirm what the
+/// canister was meant to hold.
+pub struct UploadReceipt {
+    /// The total number of bytes in the uploaded file.
+    pub total_bytes: u64,
+    /// The blake3 checksum of the entire file, as lowercase hex.
+    pub checksum: String,
+}
+
+impl UploadReceipt {
+    /// Computes a receipt for the whole file at `path`, streaming it
+    /// through a blake3 hasher instead of loading it into memory.
+    pub fn for_file(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mut hasher = blake3::Hasher::new();
+        let mut buf = [0u8; 64 * 1024];
+        let mut total_bytes = 0u64;
+
+        loop {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            total_bytes += n as u64;
+        }
+
+        Ok(UploadReceipt {
+            total_bytes,
+            checksum: hasher.finalize().to_hex().to_string(),
+        })
+    }
+
+    /// Formats the receipt as a single human-readable line.
+    pub fn as_line(&self, file_path: &str) -> String {
+        format!("{}\tsize={}\tblake3={}", file_path, self.total_bytes, self.checksum)
+    }
+
+    /// Returns the receipt path that sits alongside `file_path`.
+    pub fn path_for(file_path: &Path) -> PathBuf {
+        let mut path = file_path.as_os_str().to_owned();
+        path.push(".receipt");
+        PathBuf::from(path)
+    }
+
+    /// Writes the receipt line to `<file_path>.receipt`.
+    pub fn write(&self, file_path: &Path) -> io::Result<()> {
+        let mut file = File::create(Self::path_for(file_path))?;
+        writeln!(file, "{}", self.as_line(&file_path.to_string_lossy()))
+    }
+}